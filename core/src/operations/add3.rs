@@ -0,0 +1,147 @@
+use core::borrow::Borrow;
+use core::borrow::BorrowMut;
+use p3_air::AirBuilder;
+use p3_field::Field;
+use std::mem::size_of;
+use valida_derive::AlignedBorrow;
+
+use crate::air::CurtaAirBuilder;
+use crate::air::Word;
+
+use crate::bytes::ByteLookupEvent;
+use crate::bytes::ByteOpcode;
+use crate::runtime::Segment;
+use p3_field::AbstractField;
+
+/// A set of columns needed to compute the add of three words.
+///
+/// Unlike `AddOperation`, the carry out of each byte limb can be 0, 1, or 2, since three bytes
+/// plus an incoming carry of at most 2 can sum to as much as 767. The carry is therefore tracked
+/// with a one-hot encoding (`is_carry_0`, `is_carry_1`, `is_carry_2`) rather than a single boolean
+/// column.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Add3Operation<T> {
+    /// The result of `a + b + c`.
+    pub value: Word<T>,
+
+    /// Indicates whether the carry out of a limb is 0.
+    pub is_carry_0: Word<T>,
+
+    /// Indicates whether the carry out of a limb is 1.
+    pub is_carry_1: Word<T>,
+
+    /// Indicates whether the carry out of a limb is 2.
+    pub is_carry_2: Word<T>,
+
+    /// The carry out of each limb, equal to `is_carry_1 + 2 * is_carry_2`.
+    pub carry: Word<T>,
+}
+
+impl<F: Field> Add3Operation<F> {
+    pub fn populate(&mut self, segment: &mut Segment, a_u32: u32, b_u32: u32, c_u32: u32) -> u32 {
+        let expected = a_u32.wrapping_add(b_u32).wrapping_add(c_u32);
+        self.value = Word::from(expected);
+        let a = a_u32.to_le_bytes();
+        let b = b_u32.to_le_bytes();
+        let c = c_u32.to_le_bytes();
+
+        let mut carry = [0u8; 4];
+        let mut prev_carry = 0u32;
+        for i in 0..4 {
+            let sum = (a[i] as u32) + (b[i] as u32) + (c[i] as u32) + prev_carry;
+            let limb_carry = sum / 256;
+            carry[i] = limb_carry as u8;
+            prev_carry = limb_carry;
+
+            match limb_carry {
+                0 => self.is_carry_0[i] = F::one(),
+                1 => self.is_carry_1[i] = F::one(),
+                2 => self.is_carry_2[i] = F::one(),
+                _ => unreachable!("carry out of a 3-operand byte add cannot exceed 2"),
+            }
+            self.carry[i] = F::from_canonical_u8(carry[i]);
+        }
+
+        // Range check
+        {
+            let bytes: Vec<u8> = a
+                .iter()
+                .chain(b.iter())
+                .chain(c.iter())
+                .chain(expected.to_le_bytes().iter())
+                .map(|x| *x)
+                .collect();
+            // The byte length is always even since each word has 4 bytes.
+            assert_eq!(bytes.len() % 2, 0);
+
+            // Pass two bytes to range check at a time.
+            for i in (0..bytes.len()).step_by(2) {
+                segment.add_byte_range_checks(bytes[i], bytes[i + 1]);
+            }
+        }
+        expected
+    }
+
+    pub fn eval<AB: CurtaAirBuilder>(
+        builder: &mut AB,
+        a: Word<AB::Var>,
+        b: Word<AB::Var>,
+        c: Word<AB::Var>,
+        cols: Add3Operation<AB::Var>,
+        is_real: AB::Var,
+    ) {
+        let one = AB::Expr::one();
+        let base = AB::F::from_canonical_u32(256);
+
+        let mut builder_is_real = builder.when(is_real);
+
+        let mut prev_carry = AB::Expr::zero();
+        for i in 0..4 {
+            // The one-hot carry indicators must sum to exactly one.
+            builder_is_real.assert_eq(
+                cols.is_carry_0[i] + cols.is_carry_1[i] + cols.is_carry_2[i],
+                one.clone(),
+            );
+            builder_is_real.assert_bool(cols.is_carry_0[i]);
+            builder_is_real.assert_bool(cols.is_carry_1[i]);
+            builder_is_real.assert_bool(cols.is_carry_2[i]);
+
+            // The carry column is the one-hot encoding evaluated as an integer.
+            let carry = cols.is_carry_1[i] + cols.is_carry_2[i] * AB::F::from_canonical_u32(2);
+            builder_is_real.assert_eq(cols.carry[i], carry.clone());
+
+            builder_is_real.assert_zero(
+                a[i] + b[i] + c[i] + prev_carry.clone() - cols.value[i] - cols.carry[i] * base,
+            );
+
+            prev_carry = cols.carry[i].into();
+        }
+        builder_is_real.assert_bool(is_real);
+
+        // Range check each byte.
+        {
+            let bytes = a
+                .0
+                .iter()
+                .chain(b.0.iter())
+                .chain(c.0.iter())
+                .chain(cols.value.0.iter())
+                .map(|x| *x)
+                .collect::<Vec<_>>();
+            for i in (0..bytes.len()).step_by(2) {
+                builder.send_byte_pair(
+                    AB::F::from_canonical_u32(ByteOpcode::Range as u32),
+                    AB::F::zero(),
+                    AB::F::zero(),
+                    bytes[i],
+                    bytes[i + 1],
+                    is_real,
+                );
+            }
+        }
+
+        // Degree 3 constraint to avoid "OodEvaluationMismatch".
+        builder.assert_zero(a[0] * b[0] * cols.value[0] - a[0] * b[0] * cols.value[0]);
+    }
+}
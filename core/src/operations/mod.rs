@@ -0,0 +1,9 @@
+mod add;
+mod add3;
+mod muladd;
+mod sub;
+
+pub use add::*;
+pub use add3::*;
+pub use muladd::*;
+pub use sub::*;
@@ -0,0 +1,178 @@
+use core::borrow::Borrow;
+use core::borrow::BorrowMut;
+use p3_air::AirBuilder;
+use p3_field::Field;
+use std::mem::size_of;
+use valida_derive::AlignedBorrow;
+
+use crate::air::CurtaAirBuilder;
+use crate::air::Word;
+
+use crate::bytes::ByteLookupEvent;
+use crate::bytes::ByteOpcode;
+use crate::runtime::Segment;
+use p3_field::AbstractField;
+
+/// A set of columns needed to compute `a * b + {hi, lo}`, a fused multiply-add over 32-bit words
+/// producing a 64-bit `{hi, lo}` result.
+///
+/// The product is built schoolbook-style: each of the 8 output byte positions accumulates the
+/// partial byte products `a[i] * b[j]` with `i + j` equal to that position, plus the incoming
+/// accumulator bytes, then the whole thing is carry-propagated across the 8 result bytes.
+///
+/// Unlike `AddOperation`'s carry, the carry out of a schoolbook byte position isn't a single bit:
+/// up to 4 byte products plus a byte of accumulator plus the incoming carry can total as much as
+/// ~261000, so the carry out of a limb can be in the low thousands. Each `carry[k]` is therefore
+/// stored as two range-checked bytes (little-endian) rather than one boolean column, and every
+/// limb (including the top one, whose carry is otherwise discarded) gets one: without bounding
+/// the carry, `sum - value = 256 * carry` is solvable for literally any `value`, since 256 is
+/// invertible in the field, so an unranged carry pins nothing.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct MulAddOperation<T> {
+    /// The schoolbook partial-byte-product sum for each of the 8 output byte positions, before
+    /// the accumulator is folded in and before carry propagation. Pinned by direct equality to
+    /// the byte products of `a`/`b`, so (unlike `carry`) it needs no range check of its own.
+    pub product: [T; 8],
+
+    /// The carry propagated out of each of the 8 result bytes, as two little-endian bytes.
+    pub carry: [[T; 2]; 8],
+
+    /// The low 32 bits of `a * b + {hi, lo}`.
+    pub lo: Word<T>,
+
+    /// The high 32 bits of `a * b + {hi, lo}`.
+    pub hi: Word<T>,
+}
+
+impl<F: Field> MulAddOperation<F> {
+    pub fn populate(&mut self, segment: &mut Segment, a_u32: u32, b_u32: u32, acc: u64) -> u64 {
+        let expected = (a_u32 as u64).wrapping_mul(b_u32 as u64).wrapping_add(acc);
+        let lo = expected as u32;
+        let hi = (expected >> 32) as u32;
+        self.lo = Word::from(lo);
+        self.hi = Word::from(hi);
+
+        let a = a_u32.to_le_bytes();
+        let b = b_u32.to_le_bytes();
+        let acc_bytes = acc.to_le_bytes();
+        let result_bytes = expected.to_le_bytes();
+
+        let mut product = [0u32; 8];
+        for i in 0..4 {
+            for j in 0..4 {
+                product[i + j] += (a[i] as u32) * (b[j] as u32);
+            }
+        }
+        for (k, p) in product.iter().enumerate() {
+            self.product[k] = F::from_canonical_u32(*p);
+        }
+
+        let mut running_carry = 0u32;
+        let mut carry_bytes = [[0u8; 2]; 8];
+        for k in 0..8 {
+            let sum = product[k] + (acc_bytes[k] as u32) + running_carry;
+            debug_assert_eq!((sum & 0xff) as u8, result_bytes[k]);
+            running_carry = sum >> 8;
+            carry_bytes[k] = (running_carry as u16).to_le_bytes();
+            self.carry[k][0] = F::from_canonical_u8(carry_bytes[k][0]);
+            self.carry[k][1] = F::from_canonical_u8(carry_bytes[k][1]);
+        }
+
+        // Range check `a`, `b`, the accumulator bytes, the result bytes, and every carry limb.
+        // This must check exactly the values `eval` range-checks, or the byte-lookup
+        // multiplicities sent by the two won't match.
+        {
+            let bytes: Vec<u8> = a
+                .iter()
+                .chain(b.iter())
+                .chain(acc_bytes.iter())
+                .chain(result_bytes.iter())
+                .chain(carry_bytes.iter().flatten())
+                .copied()
+                .collect();
+            assert_eq!(bytes.len() % 2, 0);
+            for i in (0..bytes.len()).step_by(2) {
+                segment.add_byte_range_checks(bytes[i], bytes[i + 1]);
+            }
+        }
+        expected
+    }
+
+    pub fn eval<AB: CurtaAirBuilder>(
+        builder: &mut AB,
+        a: Word<AB::Var>,
+        b: Word<AB::Var>,
+        acc_lo: Word<AB::Var>,
+        acc_hi: Word<AB::Var>,
+        cols: MulAddOperation<AB::Var>,
+        is_real: AB::Var,
+    ) {
+        let base = AB::F::from_canonical_u32(256);
+
+        let mut builder_is_real = builder.when(is_real);
+
+        // Constrain the schoolbook partial-byte-product columns.
+        for k in 0..8 {
+            let mut sum = AB::Expr::zero();
+            for i in 0..4 {
+                if k >= i && k - i < 4 {
+                    sum = sum + a[i] * b[k - i];
+                }
+            }
+            builder_is_real.assert_eq(cols.product[k], sum);
+        }
+
+        // Carry-propagate the partial products and the incoming accumulator across the 8 result
+        // bytes. Each two-byte carry is range-checked below, which is what makes this carry
+        // propagation actually bind `value` to the correct byte rather than being solvable for
+        // any byte (see the struct doc comment).
+        let mut prev_carry = AB::Expr::zero();
+        for k in 0..8 {
+            let acc_byte = if k < 4 { acc_lo[k] } else { acc_hi[k - 4] };
+            let value = if k < 4 { cols.lo[k] } else { cols.hi[k - 4] };
+            let carry = cols.carry[k][0] + cols.carry[k][1] * base;
+            builder_is_real
+                .assert_zero(cols.product[k] + acc_byte + prev_carry.clone() - value - carry.clone() * base);
+            prev_carry = carry;
+        }
+        builder_is_real.assert_bool(is_real);
+
+        // Range check each byte.
+        {
+            let bytes = a
+                .0
+                .iter()
+                .chain(b.0.iter())
+                .chain(acc_lo.0.iter())
+                .chain(acc_hi.0.iter())
+                .chain(cols.lo.0.iter())
+                .chain(cols.hi.0.iter())
+                .copied()
+                .collect::<Vec<_>>();
+            for i in (0..bytes.len()).step_by(2) {
+                builder.send_byte_pair(
+                    AB::F::from_canonical_u32(ByteOpcode::Range as u32),
+                    AB::F::zero(),
+                    AB::F::zero(),
+                    bytes[i],
+                    bytes[i + 1],
+                    is_real,
+                );
+            }
+            for carry in cols.carry.iter() {
+                builder.send_byte_pair(
+                    AB::F::from_canonical_u32(ByteOpcode::Range as u32),
+                    AB::F::zero(),
+                    AB::F::zero(),
+                    carry[0],
+                    carry[1],
+                    is_real,
+                );
+            }
+        }
+
+        // Degree 3 constraint to avoid "OodEvaluationMismatch".
+        builder.assert_zero(a[0] * b[0] * cols.lo[0] - a[0] * b[0] * cols.lo[0]);
+    }
+}
@@ -0,0 +1,134 @@
+use core::borrow::Borrow;
+use core::borrow::BorrowMut;
+use p3_air::AirBuilder;
+use p3_field::Field;
+use std::mem::size_of;
+use valida_derive::AlignedBorrow;
+
+use crate::air::CurtaAirBuilder;
+use crate::air::Word;
+
+use crate::bytes::ByteLookupEvent;
+use crate::bytes::ByteOpcode;
+use crate::runtime::Segment;
+use p3_field::AbstractField;
+
+/// A set of columns needed to compute the subtraction of two words.
+///
+/// Subtraction is computed via byte-wise borrow propagation: `value = a - b` with `borrow_out`
+/// exposed so callers can implement `overflowing_sub`/`checked_sub` semantics. `borrow_out` is
+/// one exactly when `a < b`.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SubOperation<T> {
+    /// The result of `a - b`.
+    pub value: Word<T>,
+
+    /// Trace.
+    pub borrow: [T; 3],
+
+    /// Whether the subtraction underflows, i.e. whether `a < b`.
+    pub borrow_out: T,
+}
+
+impl<F: Field> SubOperation<F> {
+    pub fn populate(&mut self, segment: &mut Segment, a_u32: u32, b_u32: u32) -> u32 {
+        let expected = a_u32.wrapping_sub(b_u32);
+        self.value = Word::from(expected);
+        let a = a_u32.to_le_bytes();
+        let b = b_u32.to_le_bytes();
+
+        let mut borrow = [0u8, 0u8, 0u8, 0u8];
+        if (a[0] as i32) - (b[0] as i32) < 0 {
+            borrow[0] = 1;
+        }
+        if (a[1] as i32) - (b[1] as i32) - (borrow[0] as i32) < 0 {
+            borrow[1] = 1;
+        }
+        if (a[2] as i32) - (b[2] as i32) - (borrow[1] as i32) < 0 {
+            borrow[2] = 1;
+        }
+        if (a[3] as i32) - (b[3] as i32) - (borrow[2] as i32) < 0 {
+            borrow[3] = 1;
+        }
+        self.borrow[0] = F::from_canonical_u8(borrow[0]);
+        self.borrow[1] = F::from_canonical_u8(borrow[1]);
+        self.borrow[2] = F::from_canonical_u8(borrow[2]);
+        self.borrow_out = F::from_canonical_u8(borrow[3]);
+
+        // Range check
+        {
+            let bytes: Vec<u8> = a
+                .iter()
+                .chain(b.iter())
+                .chain(expected.to_le_bytes().iter())
+                .map(|x| *x)
+                .collect();
+            // The byte length is always even since each word has 4 bytes.
+            assert_eq!(bytes.len() % 2, 0);
+
+            // Pass two bytes to range check at a time.
+            for i in (0..bytes.len()).step_by(2) {
+                segment.add_byte_range_checks(bytes[i], bytes[i + 1]);
+            }
+        }
+        expected
+    }
+
+    pub fn eval<AB: CurtaAirBuilder>(
+        builder: &mut AB,
+        a: Word<AB::Var>,
+        b: Word<AB::Var>,
+        cols: SubOperation<AB::Var>,
+        is_real: AB::Var,
+    ) {
+        let base = AB::F::from_canonical_u32(256);
+
+        let mut builder_is_real = builder.when(is_real);
+
+        // For each limb, assert `a[i] - b[i] - borrow[i-1] - value[i] + 256*borrow[i] = 0`, with
+        // `borrow[-1] = 0` and the final borrow exposed as `borrow_out`.
+        builder_is_real
+            .assert_zero(a[0] - b[0] - cols.value[0] + cols.borrow[0] * base);
+        builder_is_real.assert_zero(
+            a[1] - b[1] - cols.borrow[0] - cols.value[1] + cols.borrow[1] * base,
+        );
+        builder_is_real.assert_zero(
+            a[2] - b[2] - cols.borrow[1] - cols.value[2] + cols.borrow[2] * base,
+        );
+        builder_is_real.assert_zero(
+            a[3] - b[3] - cols.borrow[2] - cols.value[3] + cols.borrow_out * base,
+        );
+
+        // Assert that the borrows are boolean.
+        builder_is_real.assert_bool(cols.borrow[0]);
+        builder_is_real.assert_bool(cols.borrow[1]);
+        builder_is_real.assert_bool(cols.borrow[2]);
+        builder_is_real.assert_bool(cols.borrow_out);
+        builder_is_real.assert_bool(is_real);
+
+        // Range check each byte.
+        {
+            let bytes = a
+                .0
+                .iter()
+                .chain(b.0.iter())
+                .chain(cols.value.0.iter())
+                .map(|x| *x)
+                .collect::<Vec<_>>();
+            for i in (0..bytes.len()).step_by(2) {
+                builder.send_byte_pair(
+                    AB::F::from_canonical_u32(ByteOpcode::Range as u32),
+                    AB::F::zero(),
+                    AB::F::zero(),
+                    bytes[i],
+                    bytes[i + 1],
+                    is_real,
+                );
+            }
+        }
+
+        // Degree 3 constraint to avoid "OodEvaluationMismatch".
+        builder.assert_zero(a[0] * b[0] * cols.value[0] - a[0] * b[0] * cols.value[0]);
+    }
+}
@@ -0,0 +1,3 @@
+mod compress;
+
+pub use compress::*;